@@ -1,4 +1,5 @@
-use bevy_app::{App, Main, Plugin, Startup};
+use crate::tick_guard::TickGuard;
+use bevy_app::{App, Plugin, Startup};
 use bevy_ecs::{event::Event, system::Resource, world::World};
 use std::rc::Rc;
 use wasm_bindgen::{closure::Closure, JsCast};
@@ -35,6 +36,7 @@ pub struct WindowVisibility(bool);
 
 /// The `system_init_active_background_listener` system initializes the visibilitychange listener which runs the `Main` schedule once when hidden
 fn system_init_active_background_listener(world: &mut World) {
+    let tick_guard = world.get_resource_or_insert_with(TickGuard::default).clone();
     let world_ptr = Rc::new(world as *mut World);
     let closure = Closure::<dyn FnMut()>::new({
         let world = world_ptr.clone();
@@ -53,9 +55,9 @@ fn system_init_active_background_listener(world: &mut World) {
                 world.resource_mut::<WindowVisibility>().0 = !is_hidden;
 
                 world.trigger(*world.resource::<WindowVisibility>());
-                
+
                 if is_hidden {
-                    world.run_schedule(Main);
+                    tick_guard.tick(world);
                 }
             }
         }
@@ -1,6 +1,12 @@
+use crate::background_worker::KeepaliveSettings;
 use bevy_app::{App, Plugin, Update};
-use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_ecs::{
+    event::Event,
+    schedule::IntoSystemConfigs,
+    system::{Commands, Local, Res, ResMut, Resource},
+};
 use bevy_time::{Stopwatch, Time};
+use std::time::Duration;
 use web_sys::window;
 
 /// The `BackgroundTimerPlugin` plugin creates a timer that keeps track of the time the app isn't in focus (aka in background).
@@ -8,14 +14,28 @@ use web_sys::window;
 /// To function properly running a background worker is REQUIRED.
 ///
 /// It may prove to be useful to establish timeouts for inactive users in multiplayer games.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
-pub struct BackgroundTimerPlugin;
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct BackgroundTimerPlugin {
+    /// Thresholds of accumulated hidden time that each fire a [`BackgroundTimeout`] event the first time
+    /// they're crossed.
+    pub inactivity_thresholds: Vec<Duration>,
+
+    /// Ramps [`KeepaliveSettings::wake_delay`] up the longer the tab stays hidden, ordered ascending by
+    /// duration. The first entry is also the rate restored once the tab becomes visible again. Leave
+    /// empty to disable the back-off.
+    pub backoff_schedule: Vec<(Duration, f64)>,
+}
 
 impl Plugin for BackgroundTimerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(BackgroundTimer::default());
+        app.insert_resource(InactivityThresholds(self.inactivity_thresholds.clone()));
+        app.insert_resource(BackoffSchedule(self.backoff_schedule.clone()));
 
-        app.add_systems(Update, system_background_timer);
+        app.add_systems(
+            Update,
+            (system_background_timer, system_background_backoff).chain(),
+        );
     }
 }
 
@@ -23,13 +43,99 @@ impl Plugin for BackgroundTimerPlugin {
 #[derive(Clone, Debug, PartialEq, Default, Resource)]
 pub struct BackgroundTimer(pub Stopwatch);
 
-/// The `system_background_timer` system updates the `Stopwatch` based on the documents visibility
-fn system_background_timer(mut timer: ResMut<BackgroundTimer>, time: Res<Time>) {
+/// Thresholds of accumulated hidden time that each fire a [`BackgroundTimeout`] event the first time
+/// they're crossed.
+///
+/// Useful for establishing timeouts for inactive users in multiplayer games: warn, pause, or disconnect
+/// an idle player without polling [`BackgroundTimer`] every frame.
+#[derive(Clone, Debug, PartialEq, Default, Resource)]
+pub struct InactivityThresholds(pub Vec<Duration>);
+
+/// Maps accumulated hidden time to the `wake_delay` that should take effect once that much time has
+/// elapsed. See [`BackgroundTimerPlugin::backoff_schedule`].
+#[derive(Clone, Debug, PartialEq, Default, Resource)]
+pub struct BackoffSchedule(pub Vec<(Duration, f64)>);
+
+/// Fired the first time the accumulated hidden time tracked by [`BackgroundTimer`] crosses one of the
+/// configured [`InactivityThresholds`].
+///
+/// Edge-triggered: fired exactly once per crossing, and the fired-state resets when the tab becomes
+/// visible again, mirroring how the stopwatch itself resets.
+#[derive(Clone, Copy, Debug, PartialEq, Event)]
+pub struct BackgroundTimeout {
+    /// The threshold from [`InactivityThresholds`] that was just crossed.
+    pub threshold: Duration,
+}
+
+/// The `system_background_timer` system updates the `Stopwatch` based on the document's visibility and
+/// emits a [`BackgroundTimeout`] event the first time the accumulated hidden time crosses each configured
+/// threshold.
+fn system_background_timer(
+    mut commands: Commands,
+    mut timer: ResMut<BackgroundTimer>,
+    time: Res<Time>,
+    thresholds: Res<InactivityThresholds>,
+    mut fired: Local<Vec<bool>>,
+) {
     match window()
         .and_then(|w| w.document())
         .is_some_and(|d| d.hidden())
     {
-        true => _ = timer.0.tick(time.delta()),
-        false => timer.0.reset(),
+        true => {
+            timer.0.tick(time.delta());
+
+            fired.resize(thresholds.0.len(), false);
+            for (threshold, fired) in thresholds.0.iter().zip(fired.iter_mut()) {
+                if !*fired && timer.0.elapsed() >= *threshold {
+                    *fired = true;
+                    commands.trigger(BackgroundTimeout {
+                        threshold: *threshold,
+                    });
+                }
+            }
+        }
+        false => {
+            timer.0.reset();
+            fired.fill(false);
+        }
     };
 }
+
+/// The `system_background_backoff` system reads [`BackgroundTimer`] and, per [`BackoffSchedule`], ramps
+/// [`KeepaliveSettings::wake_delay`] up the longer the tab stays hidden, snapping back to the schedule's
+/// lowest-duration rate as soon as the tab becomes visible again. Does nothing while no schedule is
+/// configured, or while `KeepaliveSettings` isn't present (eg. `BackgroundTimerPlugin` used without
+/// `WebKeepalivePlugin`).
+fn system_background_backoff(
+    timer: Res<BackgroundTimer>,
+    schedule: Res<BackoffSchedule>,
+    settings: Option<ResMut<KeepaliveSettings>>,
+) {
+    let Some(mut settings) = settings else {
+        return;
+    };
+
+    let Some((_, foreground_wake_delay)) = schedule.0.first() else {
+        return;
+    };
+
+    let hidden = window()
+        .and_then(|w| w.document())
+        .is_some_and(|d| d.hidden());
+
+    let wake_delay = if hidden {
+        schedule
+            .0
+            .iter()
+            .filter(|(threshold, _)| *threshold <= timer.0.elapsed())
+            .last()
+            .map(|(_, wake_delay)| *wake_delay)
+            .unwrap_or(*foreground_wake_delay)
+    } else {
+        *foreground_wake_delay
+    };
+
+    if settings.wake_delay != wake_delay {
+        settings.wake_delay = wake_delay;
+    }
+}
@@ -1,25 +1,44 @@
-use bevy_app::{App, Main, Plugin, Startup};
-use bevy_ecs::{system::Resource, world::World};
+use crate::tick_guard::TickGuard;
+use bevy_app::{App, Plugin, Startup, Update};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Local, Res, Resource},
+    world::World,
+};
+use std::cell::RefCell;
 use std::rc::Rc;
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
-use web_sys::{js_sys::Array, window, Blob, Url, Worker};
+use web_sys::{
+    js_sys::{Array, Function, Object, Reflect},
+    window, AbortController, AbortSignal, Blob, Url, Worker,
+};
 
 /// The `WebKeepalivePlugin` plugin creates a web worker that runs the main schedule even when the tab is not visible.
 /// This allows a game  to keep bevy running in the background (eg. when the user is on another browser tab).
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct WebKeepalivePlugin {
-    /// The interval of time, in milliseconds, to run the `Main` schedule when a tab is hidden.
+    /// How the background worker should drive the `Main` schedule while the tab is hidden.
     ///
-    /// This interval timer can be changed after the initial value is set through the [`KeepaliveSettings`] resource.
+    /// This can be changed after the initial value is set through the [`KeepaliveSettings`] resource.
+    pub run_mode: RunMode,
+
+    /// The scheduling strategy used to wake the main thread.
     ///
-    /// The default is 16.667, or 60 updates per seconds.
-    pub initial_wake_delay: f64,
+    /// This can be changed after the initial value is set through the [`KeepaliveSettings`] resource.
+    pub backend: KeepaliveBackend,
+
+    /// The priority passed to `scheduler.postTask` when `backend` is [`KeepaliveBackend::PrioritizedTask`].
+    ///
+    /// This can be changed after the initial value is set through the [`KeepaliveSettings`] resource.
+    pub task_priority: TaskPriority,
 }
 
 impl Default for WebKeepalivePlugin {
     fn default() -> Self {
         Self {
-            initial_wake_delay: 16.667,
+            run_mode: RunMode::default(),
+            backend: KeepaliveBackend::default(),
+            task_priority: TaskPriority::default(),
         }
     }
 }
@@ -27,28 +46,118 @@ impl Default for WebKeepalivePlugin {
 impl Plugin for WebKeepalivePlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(KeepaliveSettings {
-            wake_delay: self.initial_wake_delay,
+            wake_delay: match self.run_mode {
+                RunMode::Loop { wait } => wait,
+                RunMode::Once => 0.0,
+            },
+            run_mode: self.run_mode,
+            backend: self.backend,
+            task_priority: self.task_priority,
             worker: None,
+            abort_controller: None,
         });
 
+        app.add_systems(Startup, system_init_background_worker);
         app.add_systems(
-            Startup,
-            system_init_background_worker
+            Update,
+            (system_update_background_worker, system_push_wake_delay).chain(),
         );
     }
 }
 
+/// How the background worker should drive the `Main` schedule while the tab is hidden.
+///
+/// Mirrors the shape of Bevy's `ScheduleRunnerPlugin` run mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunMode {
+    /// Keep waking the main thread roughly every `wait` milliseconds for as long as the tab stays hidden.
+    Loop {
+        /// The interval of time, in milliseconds, to run the `Main` schedule when a tab is hidden.
+        wait: f64,
+    },
+    /// Wake the main thread a single time after the tab hides, then stop.
+    Once,
+}
+
+impl Default for RunMode {
+    fn default() -> Self {
+        Self::Loop { wait: 16.667 }
+    }
+}
+
+/// The scheduling strategy used to wake the main thread while the tab is hidden.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum KeepaliveBackend {
+    /// Wakes the main thread on a plain `setInterval` running inside a dedicated worker.
+    ///
+    /// Simple and cheap, but browsers eventually clamp background timers to at least 1000ms, which
+    /// defeats the keepalive at high tick rates.
+    #[default]
+    Interval,
+
+    /// Wakes the main thread through a self-feeding `MessageChannel` ping-pong loop inside a dedicated
+    /// worker, the same trick winit uses to dodge background timer throttling.
+    ///
+    /// `port1.onmessage` immediately re-posts to `port2`, forming a tight loop that isn't subject to the
+    /// timer-throttling minimum; a wake is only forwarded to the main thread once `wake_delay` milliseconds
+    /// have elapsed since the last one, gated by `performance.now()`. This trades extra worker CPU for
+    /// precise, unthrottled wakes.
+    MessageChannel,
+
+    /// Wakes the main thread with the Prioritized Task Scheduling API (`scheduler.postTask`) instead of a
+    /// worker, re-scheduling itself after every tick at `task_priority` so it yields to more important
+    /// work while the tab is hidden.
+    ///
+    /// Falls back to [`KeepaliveBackend::Interval`] when `scheduler.postTask` isn't available.
+    PrioritizedTask,
+}
+
+/// Priority hint passed to `scheduler.postTask` for the [`KeepaliveBackend::PrioritizedTask`] backend.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum TaskPriority {
+    /// Yields to any other work; appropriate for a hidden tab's keepalive ticks.
+    #[default]
+    Background,
+    /// Runs alongside user-visible work.
+    UserVisible,
+    /// Runs ahead of everything else.
+    UserBlocking,
+}
+
+impl TaskPriority {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Background => "background",
+            Self::UserVisible => "user-visible",
+            Self::UserBlocking => "user-blocking",
+        }
+    }
+}
+
 /// The `KeepaliveSettings` resource can be used to control at runtime how the background worker operates.
 ///
-/// Please note that it currently isn't possible to change from `setTimeout` to `setInterval`.
+/// Changing `run_mode`, `backend` or `task_priority` tears down the existing worker/task and spawns a
+/// fresh one with a regenerated script. A `wake_delay` change is posted to the running worker instead.
 #[derive(Clone, Debug, PartialEq, Default, Resource)]
 pub struct KeepaliveSettings {
     /// The interval of time, in milliseconds, to run the `Main` schedule when a tab is hidden.
     ///
-    /// The default is 16.667, or 60 updates per seconds.
+    /// Under [`RunMode::Once`] it is instead the delay before the single wake, read when the worker is
+    /// spawned. Not used while `backend` is [`KeepaliveBackend::PrioritizedTask`] and `scheduler.postTask`
+    /// is available. The default is 16.667, or 60 updates per second.
     pub wake_delay: f64,
-    
+
+    /// How the background worker should drive the `Main` schedule while the tab is hidden.
+    pub run_mode: RunMode,
+
+    /// The scheduling strategy used to wake the main thread.
+    pub backend: KeepaliveBackend,
+
+    /// The priority passed to `scheduler.postTask` when `backend` is [`KeepaliveBackend::PrioritizedTask`].
+    pub task_priority: TaskPriority,
+
     worker: Option<Worker>,
+    abort_controller: Option<AbortController>,
 }
 
 unsafe impl Send for KeepaliveSettings {}
@@ -59,33 +168,93 @@ impl Drop for KeepaliveSettings {
         if let Some(worker) = &self.worker {
             worker.terminate();
         }
+        if let Some(abort_controller) = &self.abort_controller {
+            abort_controller.abort();
+        }
     }
 }
 
-/// The `system_init_timeout_background_worker` system runs at `Startup` and launches the web worker with a tick loop based on `setInterval`
-fn system_init_background_worker(world: &mut World) {
-    let mut settings = world.resource_mut::<KeepaliveSettings>();
-    let script = Blob::new_with_str_sequence(
-        &Array::of1(&JsValue::from_str(&format!(
+/// Builds the worker script for the given backend and run mode, arranged to wake the main thread
+/// roughly every `wake_delay` milliseconds while looping, or once while running `RunMode::Once`.
+///
+/// The `RunMode::Once` script doesn't arm its timeout on its own: it waits for a message from the main
+/// thread (sent once the tab is actually observed hidden, see `arm_once_on_hide`) before starting the
+/// `wake_delay` countdown, rather than racing a blind timer against an unknown hide time.
+fn build_worker_script(wake_delay: f64, backend: KeepaliveBackend, run_mode: RunMode) -> String {
+    if matches!(run_mode, RunMode::Once) {
+        return format!(
             "
-            let interval = setInterval(self.postMessage(null), {});
             self.onmessage = v => {{
-                const delay = parseInt(v);
+                if (v.data !== null) return;
+                setTimeout(() => self.postMessage(null), {});
+            }};
+            ",
+            wake_delay
+        );
+    }
+
+    match backend {
+        KeepaliveBackend::Interval => format!(
+            "
+            let interval = setInterval(() => self.postMessage(null), {});
+            self.onmessage = v => {{
+                const delay = parseInt(v.data);
                 if (isNaN(delay)) return;
                 clearInterval(interval);
-                interval = setInterval(self.postMessage(null), delay);
+                interval = setInterval(() => self.postMessage(null), delay);
             }};
             ",
-            settings.wake_delay
+            wake_delay
+        ),
+        KeepaliveBackend::MessageChannel => format!(
+            "
+            let wakeDelay = {};
+            let lastWake = 0;
+            const channel = new MessageChannel();
+            channel.port1.onmessage = () => {{
+                const now = performance.now();
+                if (now - lastWake >= wakeDelay) {{
+                    lastWake = now;
+                    self.postMessage(null);
+                }}
+                channel.port2.postMessage(null);
+            }};
+            channel.port2.postMessage(null);
+            self.onmessage = v => {{
+                const delay = parseInt(v.data);
+                if (isNaN(delay)) return;
+                wakeDelay = delay;
+            }};
+            ",
+            wake_delay
+        ),
+        KeepaliveBackend::PrioritizedTask => {
+            unreachable!("rebuild_keepalive never spawns a worker for KeepaliveBackend::PrioritizedTask")
+        }
+    }
+}
+
+/// Spawns a worker running `backend`'s script under `run_mode` and wires its messages up to run the
+/// `Main` schedule on `world` whenever the tab is hidden.
+fn spawn_worker(
+    world: &mut World,
+    wake_delay: f64,
+    backend: KeepaliveBackend,
+    run_mode: RunMode,
+) -> Worker {
+    let script = Blob::new_with_str_sequence(
+        &Array::of1(&JsValue::from_str(&build_worker_script(
+            wake_delay, backend, run_mode,
         )))
         .unchecked_into(),
     )
     .unwrap();
 
-    let worker = Worker::new(&Url::create_object_url_with_blob(&script).unwrap()).unwrap();
-    
-    settings.worker = Some(worker.clone()); // only clones the js heap ref
+    let script_url = Url::create_object_url_with_blob(&script).unwrap();
+    let worker = Worker::new(&script_url).unwrap();
+    Url::revoke_object_url(&script_url).unwrap();
 
+    let tick_guard = world.get_resource_or_insert_with(TickGuard::default).clone();
     let world_ptr = Rc::new(world as *mut World);
     let closure = Closure::<dyn FnMut()>::new({
         let world = world_ptr.clone();
@@ -100,7 +269,7 @@ fn system_init_background_worker(world: &mut World) {
                 let Some(world) = world.as_mut() else {
                     return;
                 };
-                world.run_schedule(Main);
+                tick_guard.tick(world);
             }
         }
     });
@@ -108,4 +277,259 @@ fn system_init_background_worker(world: &mut World) {
     worker.set_onmessage(Some(closure.as_ref().unchecked_ref()));
 
     closure.forget();
-}
\ No newline at end of file
+
+    if matches!(run_mode, RunMode::Once) {
+        arm_once_on_hide(&worker);
+    }
+
+    worker
+}
+
+/// Arms `worker`'s `RunMode::Once` timeout the first time the tab is observed hidden, rather than firing
+/// it blindly at spawn time: if the tab is already hidden, triggers immediately; otherwise waits for the
+/// next `visibilitychange` and removes the listener once it fires, since `RunMode::Once` only ever wakes
+/// the main thread a single time for the worker's lifetime.
+fn arm_once_on_hide(worker: &Worker) {
+    let Some(document) = window().and_then(|w| w.document()) else {
+        return;
+    };
+
+    if document.hidden() {
+        let _ = worker.post_message(&JsValue::NULL);
+        return;
+    }
+
+    let listener: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
+    let closure = Closure::<dyn FnMut()>::new({
+        let worker = worker.clone();
+        let document = document.clone();
+        let listener = listener.clone();
+        move || {
+            if !document.hidden() {
+                return;
+            }
+
+            let _ = worker.post_message(&JsValue::NULL);
+
+            if let Some(closure) = listener.borrow().as_ref() {
+                let _ = document.remove_event_listener_with_callback(
+                    "visibilitychange",
+                    closure.as_ref().unchecked_ref(),
+                );
+            }
+        }
+    });
+
+    document
+        .add_event_listener_with_callback("visibilitychange", closure.as_ref().unchecked_ref())
+        .expect("Unable to register event listener");
+
+    *listener.borrow_mut() = Some(closure);
+}
+
+/// Returns `true` if `scheduler.postTask` looks available on the global `window`.
+fn scheduler_available() -> bool {
+    let Some(window) = window() else {
+        return false;
+    };
+    let window: JsValue = window.into();
+    Reflect::get(&window, &JsValue::from_str("scheduler"))
+        .map(|scheduler| !scheduler.is_undefined())
+        .unwrap_or(false)
+}
+
+/// Calls `window.scheduler.postTask(callback, { priority, signal })`, scheduling `callback` to run once.
+fn scheduler_post_task(priority: TaskPriority, signal: &AbortSignal, callback: &Closure<dyn FnMut()>) {
+    let Some(window) = window() else {
+        return;
+    };
+    let window: JsValue = window.into();
+    let Ok(scheduler) = Reflect::get(&window, &JsValue::from_str("scheduler")) else {
+        return;
+    };
+    let Ok(post_task) = Reflect::get(&scheduler, &JsValue::from_str("postTask")) else {
+        return;
+    };
+    let Ok(post_task) = post_task.dyn_into::<Function>() else {
+        return;
+    };
+
+    let options = Object::new();
+    let _ = Reflect::set(
+        &options,
+        &JsValue::from_str("priority"),
+        &JsValue::from_str(priority.as_str()),
+    );
+    let _ = Reflect::set(&options, &JsValue::from_str("signal"), signal);
+
+    let _ = post_task.call2(&scheduler, callback.as_ref().unchecked_ref(), &options);
+}
+
+/// Spawns a self-rescheduling `scheduler.postTask` loop that ticks `Main` on `world` while the tab is
+/// hidden, yielding to higher-priority work in between each tick. Under `RunMode::Once` the loop stops
+/// rescheduling itself as soon as it has ticked `Main` once, matching the worker-backed backends. Returns
+/// the `AbortController` that cancels any queued task when aborted.
+fn spawn_prioritized_task(world: &mut World, priority: TaskPriority, run_mode: RunMode) -> AbortController {
+    let tick_guard = world.get_resource_or_insert_with(TickGuard::default).clone();
+    let world_ptr = Rc::new(world as *mut World);
+    let abort_controller = AbortController::new().unwrap();
+    let signal = abort_controller.signal();
+
+    let slot: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
+
+    let tick = {
+        let slot = slot.clone();
+        let world = world_ptr.clone();
+        let signal = signal.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            if window()
+                .and_then(|w| w.document())
+                .is_some_and(|d| !d.hidden())
+            {
+                if let Some(callback) = slot.borrow().as_ref() {
+                    scheduler_post_task(priority, &signal, callback);
+                }
+                return;
+            }
+
+            unsafe {
+                if let Some(world) = world.as_mut() {
+                    tick_guard.tick(world);
+                }
+            }
+
+            if matches!(run_mode, RunMode::Once) {
+                return;
+            }
+
+            if let Some(callback) = slot.borrow().as_ref() {
+                scheduler_post_task(priority, &signal, callback);
+            }
+        })
+    };
+
+    *slot.borrow_mut() = Some(tick);
+
+    if let Some(callback) = slot.borrow().as_ref() {
+        scheduler_post_task(priority, &signal, callback);
+    }
+
+    abort_controller
+}
+
+/// Tears down any worker/task stored on `settings` and spawns a fresh one matching the current
+/// configuration, falling back to [`KeepaliveBackend::Interval`] if [`KeepaliveBackend::PrioritizedTask`]
+/// was requested but `scheduler.postTask` isn't available.
+fn rebuild_keepalive(
+    world: &mut World,
+    wake_delay: f64,
+    backend: KeepaliveBackend,
+    run_mode: RunMode,
+    task_priority: TaskPriority,
+) {
+    {
+        let mut settings = world.resource_mut::<KeepaliveSettings>();
+        if let Some(worker) = settings.worker.take() {
+            worker.terminate();
+        }
+        if let Some(abort_controller) = settings.abort_controller.take() {
+            abort_controller.abort();
+        }
+    }
+
+    let (worker, abort_controller) = match backend {
+        KeepaliveBackend::PrioritizedTask if scheduler_available() => (
+            None,
+            Some(spawn_prioritized_task(world, task_priority, run_mode)),
+        ),
+        KeepaliveBackend::PrioritizedTask => (
+            Some(spawn_worker(
+                world,
+                wake_delay,
+                KeepaliveBackend::Interval,
+                run_mode,
+            )),
+            None,
+        ),
+        backend => (Some(spawn_worker(world, wake_delay, backend, run_mode)), None),
+    };
+
+    let mut settings = world.resource_mut::<KeepaliveSettings>();
+    settings.worker = worker;
+    settings.abort_controller = abort_controller;
+}
+
+/// The `system_init_background_worker` system runs at `Startup` and launches the configured scheduling
+/// backend.
+fn system_init_background_worker(world: &mut World) {
+    let (wake_delay, backend, run_mode, task_priority) = {
+        let settings = world.resource::<KeepaliveSettings>();
+        (
+            settings.wake_delay,
+            settings.backend,
+            settings.run_mode,
+            settings.task_priority,
+        )
+    };
+
+    rebuild_keepalive(world, wake_delay, backend, run_mode, task_priority);
+}
+
+/// The `system_update_background_worker` system watches [`KeepaliveSettings`] for runtime changes to
+/// `backend`, `run_mode` or `task_priority` and, when any of them change, tears down the existing
+/// worker/task and spawns a fresh one running the newly requested configuration.
+///
+/// The first `Update` tick only records the settings `system_init_background_worker` already built the
+/// worker for at `Startup`, rather than comparing against `Local`'s type-default value, so a non-default
+/// startup configuration doesn't look like a runtime change and trigger a redundant rebuild.
+///
+/// `wake_delay` changes are handled separately by `system_push_wake_delay`, which forwards them to an
+/// already-running worker instead of rebuilding it.
+fn system_update_background_worker(
+    world: &mut World,
+    mut last_config: Local<Option<(KeepaliveBackend, RunMode, TaskPriority)>>,
+) {
+    let config = {
+        let settings = world.resource::<KeepaliveSettings>();
+        (settings.backend, settings.run_mode, settings.task_priority)
+    };
+
+    if *last_config == Some(config) {
+        return;
+    }
+
+    let first_run = last_config.is_none();
+    *last_config = Some(config);
+
+    if first_run {
+        return;
+    }
+
+    let (backend, run_mode, task_priority) = config;
+    let wake_delay = world.resource::<KeepaliveSettings>().wake_delay;
+
+    rebuild_keepalive(world, wake_delay, backend, run_mode, task_priority);
+}
+
+/// Forwards a new `wake_delay` to a running worker via `postMessage`, without tearing it down, relying
+/// on the worker script's own `onmessage` handler to re-arm its interval. Has no effect for backends not
+/// backed by a `Worker` (eg. [`KeepaliveBackend::PrioritizedTask`] while `scheduler.postTask` is
+/// available).
+pub(crate) fn push_wake_delay(settings: &KeepaliveSettings, wake_delay: f64) {
+    if let Some(worker) = &settings.worker {
+        let _ = worker.post_message(&JsValue::from_f64(wake_delay));
+    }
+}
+
+/// The `system_push_wake_delay` system watches [`KeepaliveSettings::wake_delay`] for runtime changes and
+/// forwards them to the running worker via `postMessage`, without the teardown/rebuild
+/// `system_update_background_worker` performs for backend/run_mode/task_priority changes.
+fn system_push_wake_delay(settings: Res<KeepaliveSettings>, mut last_wake_delay: Local<u64>) {
+    let wake_delay_bits = settings.wake_delay.to_bits();
+    if wake_delay_bits == *last_wake_delay {
+        return;
+    }
+    *last_wake_delay = wake_delay_bits;
+
+    push_wake_delay(&settings, settings.wake_delay);
+}
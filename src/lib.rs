@@ -1,5 +1,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+mod tick_guard;
+
 #[cfg(feature = "listener")]
 mod background_listener;
 #[cfg(feature = "listener")]
@@ -10,7 +12,12 @@ pub use background_listener::{VisibilityChangeListenerPlugin, WindowVisibility};
 mod background_timer;
 #[cfg(feature = "timer")]
 #[cfg_attr(docsrs, doc(cfg(feature = "timer")))]
-pub use background_timer::{BackgroundTimer, BackgroundTimerPlugin};
+pub use background_timer::{
+    BackgroundTimeout, BackgroundTimer, BackgroundTimerPlugin, BackoffSchedule,
+    InactivityThresholds,
+};
 
 mod background_worker;
-pub use background_worker::{KeepaliveSettings, WebKeepalivePlugin};
+pub use background_worker::{
+    KeepaliveBackend, KeepaliveSettings, RunMode, TaskPriority, WebKeepalivePlugin,
+};
@@ -0,0 +1,39 @@
+use bevy_app::Main;
+use bevy_ecs::{system::Resource, world::World};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Guards `world.run_schedule(Main)` against reentrant calls made from overlapping JS callbacks (eg. a
+/// worker wake delivered while a listener-triggered tick is still on the stack, or a long frame that
+/// lets a second wake arrive before the first `Main` run returns).
+///
+/// Only one `Main` run is ever allowed on the stack at a time. A wake that arrives mid-tick is
+/// recorded rather than run immediately, and honoured with exactly one extra tick once the in-flight
+/// run returns, so `&mut World` is never aliased.
+#[derive(Clone, Default, Resource)]
+pub(crate) struct TickGuard {
+    ticking: Rc<Cell<bool>>,
+    pending: Rc<Cell<bool>>,
+}
+
+unsafe impl Send for TickGuard {}
+unsafe impl Sync for TickGuard {}
+
+impl TickGuard {
+    /// Runs the `Main` schedule on `world`, or, if a run is already in progress, records the wake and
+    /// returns immediately so the in-flight run can pick it up.
+    pub(crate) fn tick(&self, world: &mut World) {
+        if self.ticking.replace(true) {
+            self.pending.set(true);
+            return;
+        }
+
+        world.run_schedule(Main);
+
+        if self.pending.replace(false) {
+            world.run_schedule(Main);
+        }
+
+        self.ticking.set(false);
+    }
+}